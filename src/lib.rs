@@ -0,0 +1,313 @@
+use num::Complex;
+use rayon::prelude::*;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FractalKind {
+    Mandelbrot,
+    Multibrot3,
+    BurningShip,
+}
+
+impl FromStr for FractalKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mandelbrot" => Ok(FractalKind::Mandelbrot),
+            "multibrot3" => Ok(FractalKind::Multibrot3),
+            "burning-ship" => Ok(FractalKind::BurningShip),
+            _ => Err(format!("unrecognized fractal kind '{}'", s)),
+        }
+    }
+}
+
+/// Selects how escape counts are turned into output bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    /// 8-bit grayscale, one byte per pixel, with visible iteration-count banding.
+    Grayscale,
+    /// 24-bit RGB from a smoothed (fractional) escape count, one sinusoidal gradient.
+    Sinusoidal,
+}
+
+impl ColorMode {
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            ColorMode::Grayscale => 1,
+            ColorMode::Sinusoidal => 3,
+        }
+    }
+}
+
+impl FromStr for ColorMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "grayscale" => Ok(ColorMode::Grayscale),
+            "sinusoidal" => Ok(ColorMode::Sinusoidal),
+            _ => Err(format!("unrecognized color mode '{}'", s)),
+        }
+    }
+}
+
+/// Parameters needed to render one rectangular region of a fractal into a pixel buffer.
+pub struct RenderConfig {
+    pub bounds: (usize, usize),
+    pub top_left: Complex<f64>,
+    pub bottom_right: Complex<f64>,
+    pub limit: usize,
+    pub kind: FractalKind,
+    pub color_mode: ColorMode,
+}
+
+/// Renders the region between `config.top_left` and `config.bottom_right` and returns
+/// it as a freshly allocated, row-major buffer using `config.color_mode`'s byte layout.
+pub fn render_region(config: &RenderConfig) -> Vec<u8> {
+    let bytes_per_pixel = config.color_mode.bytes_per_pixel();
+    let mut pixels = vec![0u8; config.bounds.0 * config.bounds.1 * bytes_per_pixel];
+
+    for row in 0..config.bounds.1 {
+        for column in 0..config.bounds.0 {
+            let offset = (row * config.bounds.0 + column) * bytes_per_pixel;
+            write_pixel(config, (column, row), &mut pixels[offset..offset + bytes_per_pixel]);
+        }
+    }
+
+    pixels
+}
+
+/// Same as [`render_region`], but splits the pixel buffer across threads with Rayon,
+/// computing each row's points in parallel.
+pub fn render_region_rayon(config: &RenderConfig) -> Vec<u8> {
+    let bytes_per_pixel = config.color_mode.bytes_per_pixel();
+    let mut pixels = vec![0u8; config.bounds.0 * config.bounds.1 * bytes_per_pixel];
+
+    pixels
+        .par_chunks_mut(config.bounds.0 * bytes_per_pixel)
+        .enumerate()
+        .for_each(|(row, band)| {
+            for column in 0..config.bounds.0 {
+                let offset = column * bytes_per_pixel;
+                write_pixel(config, (column, row), &mut band[offset..offset + bytes_per_pixel]);
+            }
+        });
+
+    pixels
+}
+
+fn write_pixel(config: &RenderConfig, pixel: (usize, usize), out: &mut [u8]) {
+    let point = pixel_to_point(config.bounds, pixel, config.top_left, config.bottom_right);
+    match config.color_mode {
+        ColorMode::Grayscale => {
+            out[0] = match escape_time(config.kind, point, config.limit) {
+                None => 0,
+                Some(count) => 255 - (count * 255 / config.limit) as u8,
+            };
+        }
+        ColorMode::Sinusoidal => {
+            let (r, g, b) = match escape_time_smooth(config.kind, point, config.limit) {
+                None => (0, 0, 0),
+                Some(mu) => sinusoidal_color(mu),
+            };
+            out[0] = r;
+            out[1] = g;
+            out[2] = b;
+        }
+    }
+}
+
+fn step(kind: FractalKind, z: Complex<f64>, c: Complex<f64>) -> Complex<f64> {
+    match kind {
+        FractalKind::Mandelbrot => z * z + c,
+        FractalKind::Multibrot3 => z * z * z + c,
+        FractalKind::BurningShip => {
+            let z = Complex {
+                re: z.re.abs(),
+                im: z.im.abs(),
+            };
+            z * z + c
+        }
+    }
+}
+
+pub(crate) fn escape_time(kind: FractalKind, c: Complex<f64>, limit: usize) -> Option<usize> {
+    let mut z = Complex { re: 0.0, im: 0.0 };
+    for i in 0..limit {
+        if z.norm_sqr() > 4.0 {
+            return Some(i);
+        }
+        z = step(kind, z, c);
+    }
+
+    None
+}
+
+/// Like [`escape_time`], but continues a couple of iterations past the bailout and
+/// returns a fractional iteration count `mu`, which eliminates the stair-step banding
+/// that plain integer escape counts produce.
+pub(crate) fn escape_time_smooth(kind: FractalKind, c: Complex<f64>, limit: usize) -> Option<f64> {
+    let mut z = Complex { re: 0.0, im: 0.0 };
+    for i in 0..limit {
+        if z.norm_sqr() > 4.0 {
+            for _ in 0..2 {
+                z = step(kind, z, c);
+            }
+            let mu = i as f64 + 1.0 - z.norm().log2().log2();
+            return Some(mu);
+        }
+        z = step(kind, z, c);
+    }
+
+    None
+}
+
+/// Maps a fractional escape count to an RGB triplet using a sinusoidal gradient, so
+/// nearby `mu` values produce nearby colors instead of discrete grayscale bands.
+fn sinusoidal_color(mu: f64) -> (u8, u8, u8) {
+    let t = mu * 0.1;
+    let channel = |phase: f64| ((0.5 + 0.5 * (t + phase).sin()) * 255.0) as u8;
+    (channel(0.0), channel(2.0), channel(4.0))
+}
+
+/// Maps a pixel coordinate to the point in the complex plane it represents, given the
+/// region `top_left`/`bottom_right` covers. Exposed so alternate rendering strategies
+/// (e.g. a custom thread-banding scheme) can compute sub-region bounds.
+pub fn pixel_to_point(
+    bounds: (usize, usize),
+    pixel: (usize, usize),
+    top_left: Complex<f64>,
+    bottom_right: Complex<f64>,
+) -> Complex<f64> {
+    let (width, heigth) = (bottom_right.re - top_left.re, top_left.im - bottom_right.im);
+    Complex {
+        re: top_left.re + pixel.0 as f64 * width / bounds.0 as f64,
+        im: top_left.im - pixel.1 as f64 * heigth / bounds.1 as f64,
+    }
+}
+
+#[cfg(feature = "wasm")]
+mod wasm {
+    use super::*;
+    use wasm_bindgen::prelude::*;
+
+    /// Renders a fractal region and returns an RGBA byte buffer ready to blit into an
+    /// HTML canvas via `ImageData`. Grayscale escape counts are replicated across the
+    /// R, G and B channels with full alpha; sinusoidal output is used as-is.
+    #[wasm_bindgen]
+    pub fn render_region_rgba(
+        width: usize,
+        height: usize,
+        top_left_re: f64,
+        top_left_im: f64,
+        bottom_right_re: f64,
+        bottom_right_im: f64,
+        limit: usize,
+        kind: &str,
+        color_mode: &str,
+    ) -> Vec<u8> {
+        let config = RenderConfig {
+            bounds: (width, height),
+            top_left: Complex {
+                re: top_left_re,
+                im: top_left_im,
+            },
+            bottom_right: Complex {
+                re: bottom_right_re,
+                im: bottom_right_im,
+            },
+            limit,
+            kind: kind.parse().unwrap_or(FractalKind::Mandelbrot),
+            color_mode: color_mode.parse().unwrap_or(ColorMode::Grayscale),
+        };
+
+        let pixels = render_region(&config);
+        let mut rgba = Vec::with_capacity(width * height * 4);
+        for chunk in pixels.chunks(config.color_mode.bytes_per_pixel()) {
+            match chunk {
+                [gray] => rgba.extend_from_slice(&[*gray, *gray, *gray, 255]),
+                [r, g, b] => rgba.extend_from_slice(&[*r, *g, *b, 255]),
+                _ => unreachable!("bytes_per_pixel is always 1 or 3"),
+            }
+        }
+        rgba
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fractal_kind_from_str() {
+        assert_eq!(FractalKind::from_str("mandelbrot"), Ok(FractalKind::Mandelbrot));
+        assert_eq!(FractalKind::from_str("multibrot3"), Ok(FractalKind::Multibrot3));
+        assert_eq!(FractalKind::from_str("burning-ship"), Ok(FractalKind::BurningShip));
+        assert!(FractalKind::from_str("julia").is_err());
+    }
+
+    #[test]
+    fn test_color_mode_from_str() {
+        assert_eq!(ColorMode::from_str("grayscale"), Ok(ColorMode::Grayscale));
+        assert_eq!(ColorMode::from_str("sinusoidal"), Ok(ColorMode::Sinusoidal));
+        assert!(ColorMode::from_str("rainbow").is_err());
+    }
+
+    #[test]
+    fn test_pixel_to_point() {
+        assert_eq!(
+            pixel_to_point(
+                (100, 200),
+                (25, 175),
+                Complex { re: -1.0, im: 1.0 },
+                Complex { re: 1.0, im: -1.0 }
+            ),
+            Complex {
+                re: -0.5,
+                im: -0.75
+            }
+        );
+    }
+
+    #[test]
+    fn test_render_region_fills_buffer() {
+        let config = RenderConfig {
+            bounds: (10, 10),
+            top_left: Complex { re: -1.0, im: 1.0 },
+            bottom_right: Complex { re: 1.0, im: -1.0 },
+            limit: 255,
+            kind: FractalKind::Mandelbrot,
+            color_mode: ColorMode::Grayscale,
+        };
+        assert_eq!(render_region(&config).len(), 100);
+    }
+
+    #[test]
+    fn test_render_region_sinusoidal_is_rgb() {
+        let config = RenderConfig {
+            bounds: (10, 10),
+            top_left: Complex { re: -1.0, im: 1.0 },
+            bottom_right: Complex { re: 1.0, im: -1.0 },
+            limit: 255,
+            kind: FractalKind::Mandelbrot,
+            color_mode: ColorMode::Sinusoidal,
+        };
+        assert_eq!(render_region(&config).len(), 300);
+    }
+
+    #[test]
+    fn test_render_region_rayon_matches_sequential() {
+        for color_mode in [ColorMode::Grayscale, ColorMode::Sinusoidal] {
+            let config = RenderConfig {
+                bounds: (37, 23),
+                top_left: Complex { re: -2.0, im: 1.25 },
+                bottom_right: Complex { re: 1.0, im: -1.25 },
+                limit: 255,
+                kind: FractalKind::BurningShip,
+                color_mode,
+            };
+            assert_eq!(render_region(&config), render_region_rayon(&config));
+        }
+    }
+}
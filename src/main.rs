@@ -1,5 +1,9 @@
+use image::jpeg::JPEGEncoder;
 use image::png::PNGEncoder;
 use image::ColorType;
+use mandelbrot::{
+    pixel_to_point, render_region, render_region_rayon, ColorMode, FractalKind, RenderConfig,
+};
 use num::Complex;
 use std::env;
 use std::fs::File;
@@ -22,18 +26,41 @@ fn main() -> ExitCode {
 }
 
 fn generate_mandelbrot_image(args: &Vec<String>) -> Result<(), Error> {
-    let render_strategy = match args.last().unwrap().as_str() {
-        "-st" => render_single_threaded,
-        _ => render_multi_threaded,
+    let mut args = args.clone();
+    let single_threaded = extract_flag(&mut args, "-st");
+    let use_rayon = extract_flag(&mut args, "--rayon");
+    let max_iterations = extract_flag_value(&mut args, "--max-iterations")
+        .map(|v| v.parse().expect("Error parsing max iterations"))
+        .unwrap_or(255);
+    let format = extract_flag_value(&mut args, "--format")
+        .map(|v| OutputFormat::from_str(&v).expect("Error parsing output format"))
+        .unwrap_or(OutputFormat::Png);
+    let color_mode = extract_flag_value(&mut args, "--color-mode")
+        .map(|v| ColorMode::from_str(&v).expect("Error parsing color mode"))
+        .unwrap_or(ColorMode::Grayscale);
+    let render_strategy = if single_threaded {
+        render_single_threaded
+    } else if use_rayon {
+        render_rayon
+    } else {
+        render_multi_threaded
     };
 
-    if let [filename, bounds_input, pair_1, pair_2] = &args[1..5] {
+    if let [filename, bounds_input, pair_1, pair_2, kind_input] = &args[1..6] {
         let bounds = parse_pair(&bounds_input, 'x').expect("Error parsing image dimensions");
         let top_left = parse_complex(&pair_1).expect("Error parsing top left corner point");
         let bottom_right = parse_complex(&pair_2).expect("Error parsing bottom right corner point");
-        let mut pixels = vec![0; bounds.0 * bounds.1];
-        render_strategy(&mut pixels, bounds, top_left, bottom_right);
-        write_image(&filename, &pixels, bounds).expect("Error writing PNG file");
+        let kind = FractalKind::from_str(&kind_input).expect("Error parsing fractal kind");
+        let config = RenderConfig {
+            bounds,
+            top_left,
+            bottom_right,
+            limit: max_iterations,
+            kind,
+            color_mode,
+        };
+        let pixels = render_strategy(&config);
+        write_image(&filename, &pixels, bounds, color_mode, format).expect("Error writing image file");
     }
 
     Ok(())
@@ -41,29 +68,59 @@ fn generate_mandelbrot_image(args: &Vec<String>) -> Result<(), Error> {
 
 fn alert_error() {
     eprintln!("");
-    eprintln!("Usage: <target path> <file name> <resolution> <top left> <bottom right>",);
-    eprintln!("Example: target/release/mandelbrot mandel.png 4000x3000 -1.20,0.35 -1,0.20");
+    eprintln!(
+        "Usage: <target path> <file name> <resolution> <top left> <bottom right> <fractal kind> [-st] [--rayon] [--max-iterations N] [--format png|jpeg] [--color-mode grayscale|sinusoidal]",
+    );
+    eprintln!("Fractal kinds: mandelbrot, multibrot3, burning-ship");
+    eprintln!("Color modes: grayscale (default), sinusoidal");
+    eprintln!(
+        "Example: target/release/mandelbrot mandel.png 4000x3000 -1.20,0.35 -1,0.20 mandelbrot --max-iterations 2000 --color-mode sinusoidal"
+    );
 }
 
-fn render_single_threaded(
-    pixels: &mut [u8],
-    bounds: (usize, usize),
-    top_left: Complex<f64>,
-    bottom_right: Complex<f64>,
-) {
+/// Removes the first occurrence of `flag` from `args`, returning whether it was present.
+fn extract_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    match args.iter().position(|a| a == flag) {
+        Some(index) => {
+            args.remove(index);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Removes `flag` and the value following it from `args`, returning that value.
+fn extract_flag_value(args: &mut Vec<String>, flag: &str) -> Option<String> {
+    let index = args.iter().position(|a| a == flag)?;
+    args.remove(index);
+    if index < args.len() {
+        Some(args.remove(index))
+    } else {
+        None
+    }
+}
+
+fn render_single_threaded(config: &RenderConfig) -> Vec<u8> {
     println!("Performing single-threaded computations");
-    render(pixels, bounds, top_left, bottom_right);
+    render_region(config)
 }
 
-fn render_multi_threaded(
-    pixels: &mut [u8],
-    bounds: (usize, usize),
-    top_left: Complex<f64>,
-    bottom_right: Complex<f64>,
-) {
+fn render_rayon(config: &RenderConfig) -> Vec<u8> {
+    println!(
+        "Performing data-parallel computations across {} threads",
+        rayon::current_num_threads()
+    );
+    render_region_rayon(config)
+}
+
+fn render_multi_threaded(config: &RenderConfig) -> Vec<u8> {
     let threads = num_cpus::get();
-    let rows_per_band = bounds.1 / threads + 1;
-    let bands: Vec<&mut [u8]> = pixels.chunks_mut(rows_per_band * bounds.0).collect();
+    let bytes_per_pixel = config.color_mode.bytes_per_pixel();
+    let rows_per_band = config.bounds.1 / threads + 1;
+    let mut pixels = vec![0u8; config.bounds.0 * config.bounds.1 * bytes_per_pixel];
+    let bands: Vec<&mut [u8]> = pixels
+        .chunks_mut(rows_per_band * config.bounds.0 * bytes_per_pixel)
+        .collect();
     println!(
         "Performing multi-threaded computations across {} threads",
         threads
@@ -71,27 +128,26 @@ fn render_multi_threaded(
     scope(|spawner| {
         for (i, band) in bands.into_iter().enumerate() {
             let top = rows_per_band * i;
-            let height = band.len() / bounds.0;
-            let band_bounds = (bounds.0, height);
-            let band_top_left = pixel_to_point(bounds, (0, top), top_left, bottom_right);
-            let band_bottom_right =
-                pixel_to_point(bounds, (bounds.0, top + height), top_left, bottom_right);
+            let height = band.len() / (config.bounds.0 * bytes_per_pixel);
+            let band_config = RenderConfig {
+                bounds: (config.bounds.0, height),
+                top_left: pixel_to_point(config.bounds, (0, top), config.top_left, config.bottom_right),
+                bottom_right: pixel_to_point(
+                    config.bounds,
+                    (config.bounds.0, top + height),
+                    config.top_left,
+                    config.bottom_right,
+                ),
+                limit: config.limit,
+                kind: config.kind,
+                color_mode: config.color_mode,
+            };
 
-            spawner.spawn(move || render(band, band_bounds, band_top_left, band_bottom_right));
+            spawner.spawn(move || band.copy_from_slice(&render_region(&band_config)));
         }
     });
-}
 
-fn escape_time(c: Complex<f64>, limit: usize) -> Option<usize> {
-    let mut z = Complex { re: 0.0, im: 0.0 };
-    for i in 0..limit {
-        if z.norm_sqr() > 4.0 {
-            return Some(i);
-        }
-        z = z * z + c;
-    }
-
-    None
+    pixels
 }
 
 fn parse_pair<T: FromStr>(s: &str, separator: char) -> Option<(T, T)> {
@@ -111,42 +167,47 @@ fn parse_complex(s: &str) -> Option<Complex<f64>> {
     }
 }
 
-fn pixel_to_point(
-    bounds: (usize, usize),
-    pixel: (usize, usize),
-    top_left: Complex<f64>,
-    bottom_right: Complex<f64>,
-) -> Complex<f64> {
-    let (width, heigth) = (bottom_right.re - top_left.re, top_left.im - bottom_right.im);
-    Complex {
-        re: top_left.re + pixel.0 as f64 * width / bounds.0 as f64,
-        im: top_left.im - pixel.1 as f64 * heigth / bounds.1 as f64,
-    }
+/// Output image format. PNG is fully supported today; JPEG is available for cases
+/// where smaller file sizes matter more than lossless output. PPM can be added the
+/// same way once there's a need for an uncompressed format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Png,
+    Jpeg,
 }
 
-fn render(
-    pixels: &mut [u8],
-    bounds: (usize, usize),
-    top_left: Complex<f64>,
-    bottom_right: Complex<f64>,
-) {
-    assert!(pixels.len() == bounds.0 * bounds.1);
-
-    for row in 0..bounds.1 {
-        for column in 0..bounds.0 {
-            let point = pixel_to_point(bounds, (column, row), top_left, bottom_right);
-            pixels[row * bounds.0 + column] = match escape_time(point, 255) {
-                None => 0,
-                Some(count) => 255 - count as u8,
-            };
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "png" => Ok(OutputFormat::Png),
+            "jpeg" | "jpg" => Ok(OutputFormat::Jpeg),
+            _ => Err(format!("unrecognized output format '{}'", s)),
         }
     }
 }
 
-fn write_image(filename: &str, pixels: &[u8], bounds: (usize, usize)) -> Result<(), Error> {
-    let output = File::create(filename)?;
-    let encoder = PNGEncoder::new(output);
-    encoder.encode(pixels, bounds.0 as u32, bounds.1 as u32, ColorType::Gray(8))?;
+fn write_image(
+    filename: &str,
+    pixels: &[u8],
+    bounds: (usize, usize),
+    color_mode: ColorMode,
+    format: OutputFormat,
+) -> Result<(), Error> {
+    let color_type = match color_mode {
+        ColorMode::Grayscale => ColorType::Gray(8),
+        ColorMode::Sinusoidal => ColorType::RGB(8),
+    };
+    let mut output = File::create(filename)?;
+    match format {
+        OutputFormat::Png => {
+            PNGEncoder::new(output).encode(pixels, bounds.0 as u32, bounds.1 as u32, color_type)?;
+        }
+        OutputFormat::Jpeg => {
+            JPEGEncoder::new(&mut output).encode(pixels, bounds.0 as u32, bounds.1 as u32, color_type)?;
+        }
+    }
 
     Ok(())
 }
@@ -175,17 +236,46 @@ fn test_parse_complex() {
 }
 
 #[test]
-fn test_pixel_to_point() {
+fn test_extract_flag() {
+    let mut args = vec!["prog".to_string(), "-st".to_string(), "file.png".to_string()];
+    assert!(extract_flag(&mut args, "-st"));
+    assert_eq!(args, vec!["prog".to_string(), "file.png".to_string()]);
+    assert!(!extract_flag(&mut args, "-st"));
+}
+
+#[test]
+fn test_extract_flag_value() {
+    let mut args = vec![
+        "prog".to_string(),
+        "--max-iterations".to_string(),
+        "2000".to_string(),
+        "file.png".to_string(),
+    ];
     assert_eq!(
-        pixel_to_point(
-            (100, 200),
-            (25, 175),
-            Complex { re: -1.0, im: 1.0 },
-            Complex { re: 1.0, im: -1.0 }
-        ),
-        Complex {
-            re: -0.5,
-            im: -0.75
-        }
+        extract_flag_value(&mut args, "--max-iterations"),
+        Some("2000".to_string())
     );
+    assert_eq!(args, vec!["prog".to_string(), "file.png".to_string()]);
+    assert_eq!(extract_flag_value(&mut args, "--max-iterations"), None);
+}
+
+#[test]
+fn test_output_format_from_str() {
+    assert_eq!(OutputFormat::from_str("png"), Ok(OutputFormat::Png));
+    assert_eq!(OutputFormat::from_str("jpeg"), Ok(OutputFormat::Jpeg));
+    assert_eq!(OutputFormat::from_str("jpg"), Ok(OutputFormat::Jpeg));
+    assert!(OutputFormat::from_str("ppm").is_err());
+}
+
+#[test]
+fn test_render_multi_threaded_matches_sequential() {
+    let config = RenderConfig {
+        bounds: (37, 23),
+        top_left: Complex { re: -2.0, im: 1.25 },
+        bottom_right: Complex { re: 1.0, im: -1.25 },
+        limit: 255,
+        kind: FractalKind::Mandelbrot,
+        color_mode: ColorMode::Grayscale,
+    };
+    assert_eq!(render_multi_threaded(&config), render_region(&config));
 }